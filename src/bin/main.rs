@@ -2,53 +2,95 @@ use std::env;
 use std::process;
 use std::io::{self, Write};
 
-use crossbeam_utils::thread;
-
 use mandelbrot::{
     parse_pair,
     parse_complex,
-    pixel_to_point,
-    render,
-    write_image
+    render_parallel,
+    render_buddhabrot,
+    write_image,
+    FractalKind,
+    FractalSpec,
+    Palette,
+    RenderStyle
 };
 
+fn print_usage(program: &str) {
+    writeln!(io::stderr(), "Usage: {} render [file] [pixels] [upper_left] [lower_right] [fractal] [palette] [limit] [smooth]", program).unwrap();
+    writeln!(io::stderr(), "       {} buddhabrot [file] [pixels] [upper_left] [lower_right] [fractal] [samples] [limit]", program).unwrap();
+    writeln!(io::stderr(), "Example: {} render mandel.png 1000x750 -1.20,0.35 -1,0.20 mandelbrot fire 255 smooth", program).unwrap();
+    writeln!(io::stderr(), "[fractal] is one of: mandelbrot, mandelbrot3, burning-ship").unwrap();
+    writeln!(io::stderr(), "[palette] is one of: grayscale, fire, blue-gold, hsv").unwrap();
+    writeln!(io::stderr(), "[limit] is the iteration limit; raise it to resolve filaments on deep zooms").unwrap();
+    writeln!(io::stderr(), "[smooth] is optional; pass 'smooth' to enable continuous coloring").unwrap();
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() != 5 {
-        writeln!(io::stderr(), "Usage: mandelbrot [file] [pixels] [upper_left] [lower_right]").unwrap();
-        writeln!(io::stderr(), "Example: {} mandel.png 1000x750 -1.20,0.35 -1,0.20", args[0]).unwrap();
+    match args.get(1).map(String::as_str) {
+        Some("render") => render_mode(&args),
+        Some("buddhabrot") => buddhabrot_mode(&args),
+        _ => {
+            print_usage(&args[0]);
+            process::exit(1);
+        }
+    }
+}
+
+fn render_mode(args: &[String]) {
+    if args.len() != 9 && args.len() != 10 {
+        print_usage(&args[0]);
         process::exit(1);
     }
 
-    let bounds = parse_pair(&args[2], 'x').expect("Error parsing image dimensions");
-    let upper_left = parse_complex(&args[3]).expect("Error parsing upper left corner point");
-    let lower_right = parse_complex(&args[4]).expect("Error parsing lower right corner point");
+    let bounds = parse_pair(&args[3], 'x').expect("Error parsing image dimensions");
+    let upper_left = parse_complex(&args[4]).expect("Error parsing upper left corner point");
+    let lower_right = parse_complex(&args[5]).expect("Error parsing lower right corner point");
+    let kind: FractalKind = args[6].parse().expect("Error parsing fractal kind");
+    let palette: Palette = args[7].parse().expect("Error parsing palette");
+    let limit: u32 = args[8].parse().expect("Error parsing iteration limit");
+    let smooth = args.get(9).map(|arg| arg == "smooth").unwrap_or(false);
 
-    let mut pixels = vec![0; bounds.0 * bounds.1];
+    let fractal = FractalSpec { kind, limit };
+    let style = RenderStyle { smooth, palette, interior: [0, 0, 0] };
 
-    let threads = 8;
-    let rows_per_band = bounds.1 / threads + 1;
+    let mut pixels = vec![0; bounds.0 * bounds.1 * 3];
 
-    let bands: Vec<&mut [u8]> = pixels
-        .chunks_mut(rows_per_band * bounds.0)
-        .collect();
+    let report_progress = |fraction: f64| {
+        eprint!("\rRendering... {:>3}%", (fraction * 100.0) as u32);
+        io::stderr().flush().unwrap();
+    };
 
-    thread::scope(|s| {
-        for (i, band) in bands.into_iter().enumerate() {
-            let top = rows_per_band * i;
-            let height = band.len() / bounds.0;
-            let band_bounds = (bounds.0, height);
-            let band_upper_left = pixel_to_point(bounds, (0, top), upper_left, lower_right);
-            let band_lower_right = pixel_to_point(bounds, (bounds.0, top + height), upper_left, lower_right);
+    render_parallel(&mut pixels, bounds, upper_left, lower_right, fractal, style, Some(&report_progress));
+    eprintln!();
 
-            s.spawn(move |_| {
-                render(band, band_bounds, band_upper_left, band_lower_right); 
-            });
-        }
-    }).unwrap();
-        
-    render(&mut pixels, bounds, upper_left, lower_right);
+    write_image(&args[2], &pixels, bounds).expect("Error writing image file");
+}
+
+fn buddhabrot_mode(args: &[String]) {
+    if args.len() != 9 {
+        print_usage(&args[0]);
+        process::exit(1);
+    }
+
+    let bounds = parse_pair(&args[3], 'x').expect("Error parsing image dimensions");
+    let upper_left = parse_complex(&args[4]).expect("Error parsing upper left corner point");
+    let lower_right = parse_complex(&args[5]).expect("Error parsing lower right corner point");
+    let kind: FractalKind = args[6].parse().expect("Error parsing fractal kind");
+    let samples: u32 = args[7].parse().expect("Error parsing sample count");
+    let limit: u32 = args[8].parse().expect("Error parsing iteration limit");
+
+    let fractal = FractalSpec { kind, limit };
+
+    let mut pixels = vec![0; bounds.0 * bounds.1 * 3];
+
+    let report_progress = |fraction: f64| {
+        eprint!("\rSampling... {:>3}%", (fraction * 100.0) as u32);
+        io::stderr().flush().unwrap();
+    };
+
+    render_buddhabrot(&mut pixels, bounds, upper_left, lower_right, samples, fractal, Some(&report_progress));
+    eprintln!();
 
-    write_image(&args[1], &pixels, bounds).expect("Error writing PNG file");
+    write_image(&args[2], &pixels, bounds).expect("Error writing image file");
 }
@@ -1,33 +1,182 @@
 use std::fs::File;
+use std::io::Write;
+use std::path::Path;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use num::Complex;
 use image::ColorType;
 use image::png::PNGEncoder;
+use rand::Rng;
+use rayon::prelude::*;
 
-/// Determine if `c` is in the Mandelbrot set or not, based in part
-/// on the `limit` parameter, which specifies how many "attempts"
-/// the program gets to figure it out.
-/// 
-/// If `c` is not a member, returns `Some(i)` where `i` is the number
-/// of iterations it took for `c` to leave the circle of radius 2 centered
-/// at the origin. If `c` is a member of the set, i.e., if we reached the 
-/// iteration limit without being able to prove that `c` is _not_ a member,
-/// return `None`
-fn escape_time(c: Complex<f64>, limit: u32) -> Option<u32> {
+/// The family of escape-time fractal to compute.
+///
+/// Each variant picks the recurrence that `escape_time` iterates on `z`
+/// before testing it against the radius-2 escape circle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FractalKind {
+    /// The classic `z = z*z + c` recurrence.
+    Mandelbrot,
+    /// The cubic `z = z*z*z + c` recurrence.
+    Mandelbrot3,
+    /// Folds `z` into the first quadrant before squaring: `z = |z.re|, |z.im|`
+    /// then `z = z*z + c`.
+    BurningShip,
+}
+
+impl FromStr for FractalKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mandelbrot" => Ok(FractalKind::Mandelbrot),
+            "mandelbrot3" => Ok(FractalKind::Mandelbrot3),
+            "burning-ship" => Ok(FractalKind::BurningShip),
+            _ => Err(format!("unrecognized fractal kind: '{}'", s)),
+        }
+    }
+}
+
+/// A color scheme mapping a continuous escape count to an RGB color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Palette {
+    /// `count` maps directly to a shade of gray.
+    Grayscale,
+    /// Black, through red, orange and pale yellow, to white.
+    Fire,
+    /// Deep blue, through teal, to warm gold.
+    BlueGold,
+    /// A full hue sweep around the color wheel.
+    Hsv,
+}
+
+impl FromStr for Palette {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "grayscale" => Ok(Palette::Grayscale),
+            "fire" => Ok(Palette::Fire),
+            "blue-gold" => Ok(Palette::BlueGold),
+            "hsv" => Ok(Palette::Hsv),
+            _ => Err(format!("unrecognized palette: '{}'", s)),
+        }
+    }
+}
+
+impl Palette {
+    /// Map a continuous escape count, clamped to `0.0..=255.0`, to an RGB
+    /// color under this palette.
+    fn color(&self, count: f64) -> [u8; 3] {
+        let count = count.clamp(0.0, 255.0);
+        let t = count / 255.0;
+
+        match self {
+            Palette::Grayscale => {
+                let v = (255.0 - count) as u8;
+                [v, v, v]
+            }
+            Palette::Fire => {
+                let r = (t * 255.0) as u8;
+                let g = (t * t * 255.0) as u8;
+                let b = (t.powi(4) * 255.0) as u8;
+                [r, g, b]
+            }
+            Palette::BlueGold => {
+                let r = (t * 212.0) as u8;
+                let g = (40.0 + t * 175.0).min(255.0) as u8;
+                let b = ((1.0 - t) * 200.0) as u8;
+                [r, g, b]
+            }
+            Palette::Hsv => hsv_to_rgb(t * 360.0),
+        }
+    }
+}
+
+/// Convert a hue in `0.0..360.0` (full saturation, full value) to RGB.
+fn hsv_to_rgb(hue: f64) -> [u8; 3] {
+    let h_prime = hue / 60.0;
+    let x = 1.0 - (h_prime % 2.0 - 1.0).abs();
+
+    let (r, g, b) = match h_prime as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+
+    [(r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8]
+}
+
+/// Which fractal family to iterate, and how many iterations `escape_time`
+/// gets before giving up and declaring a point a member of the set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FractalSpec {
+    pub kind: FractalKind,
+    pub limit: u32,
+}
+
+/// Apply one iteration of the `kind` fractal's recurrence to `z`.
+fn step(z: Complex<f64>, c: Complex<f64>, kind: FractalKind) -> Complex<f64> {
+    match kind {
+        FractalKind::Mandelbrot => z * z + c,
+        FractalKind::Mandelbrot3 => z * z * z + c,
+        FractalKind::BurningShip => {
+            let folded = Complex { re: z.re.abs(), im: z.im.abs() };
+            folded * folded + c
+        }
+    }
+}
+
+/// The outcome of testing a point for escape: the integer iteration `count`
+/// at which `z` first left the circle of radius 2, together with the final
+/// `z` itself (iterated a couple of steps past escape so its norm is
+/// comfortably above 2, which `smooth_escape_count` needs to behave well).
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Escape {
+    count: u32,
+    z: Complex<f64>,
+}
+
+/// Determine if `c` is in `fractal`'s set or not, based in part on
+/// `fractal.limit`, which specifies how many "attempts" the program gets
+/// to figure it out.
+///
+/// If `c` is not a member, returns `Some(escape)` where `escape.count` is
+/// the number of iterations it took for `c` to leave the circle of radius 2
+/// centered at the origin. If `c` is a member of the set, i.e., if we
+/// reached the iteration limit without being able to prove that `c` is
+/// _not_ a member, return `None`
+fn escape_time(c: Complex<f64>, fractal: FractalSpec) -> Option<Escape> {
     let mut z = Complex { re: 0.0, im: 0.0 };
 
-    for i in 0..limit {
-        z = z * z + c;
+    for i in 0..fractal.limit {
+        z = step(z, c, fractal.kind);
 
         if z.norm_sqr() > 4.0 {
-            return Some(i);
+            // Iterate a couple more steps so `z.norm()` is comfortably
+            // above 2 and the logarithms in `smooth_escape_count` behave.
+            for _ in 0..2 {
+                z = step(z, c, fractal.kind);
+            }
+
+            return Some(Escape { count: i, z });
         }
     }
 
     None
 }
 
+/// Turn an escape's integer iteration count into a continuous one, which
+/// produces smooth color gradients instead of the visible banding that
+/// comes from coloring by the raw integer count.
+fn smooth_escape_count(escape: Escape) -> f64 {
+    escape.count as f64 + 1.0 - (escape.z.norm().ln().ln() / 2f64.ln())
+}
+
 /// Parse string `s` as a coordinate pair, e.g., `"400x600"` or `"1.0,0.5"`.
 /// 
 /// Specifically, `s` should have the form <left><sep><right>, where <sep> is
@@ -77,52 +226,286 @@ fn pixel_to_point(
     }
 }
 
+/// Given a point on the complex plane, return the pixel it falls on in an
+/// image of the given `bounds`, or `None` if the point lies outside the
+/// `upper_left`/`lower_right` rectangle.
+///
+/// This is the inverse of `pixel_to_point`.
+fn point_to_pixel(
+    bounds: (usize, usize),
+    point: Complex<f64>,
+    upper_left: Complex<f64>,
+    lower_right: Complex<f64>
+) -> Option<(usize, usize)> {
+    let (width, height) = (lower_right.re - upper_left.re, upper_left.im - lower_right.im);
+
+    let col = (point.re - upper_left.re) / width * bounds.0 as f64;
+    let row = (upper_left.im - point.im) / height * bounds.1 as f64;
+
+    if col < 0.0 || row < 0.0 {
+        return None;
+    }
+
+    let (col, row) = (col as usize, row as usize);
+
+    if col < bounds.0 && row < bounds.1 {
+        Some((col, row))
+    } else {
+        None
+    }
+}
+
+/// How to turn an escape outcome into a color: whether to use continuous
+/// (smooth) or raw integer escape counts, which `Palette` to shade escaping
+/// points with, and which RGB color to use for points inside the set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderStyle {
+    pub smooth: bool,
+    pub palette: Palette,
+    pub interior: [u8; 3],
+}
+
 /// Render a rectangle of the Mandelbrot set into a buffer of pixels.
-/// 
+///
 /// The `bounds` arguments gives the width and height of the `pixels` buffer,
-/// which holds one grayscale pixel per byte. The `upper_left` and `lower_right`
+/// which holds one RGB pixel per 3 bytes. The `upper_left` and `lower_right`
 /// arguments specify points on the complex plane corresponding to the upper-
-/// left and lower-right corners of the pixel buffer.
+/// left and lower-right corners of the pixel buffer. `fractal` picks the
+/// recurrence and iteration limit; `style` picks the coloring.
+///
+/// `fractal.limit` is decoupled from the 8-bit output range by scaling each
+/// escape count into `0.0..=255.0` before handing it to the palette, so
+/// raising it for a deep zoom doesn't just truncate the coloring.
 pub fn render(
     pixels: &mut [u8],
     bounds: (usize, usize),
     upper_left: Complex<f64>,
-    lower_right: Complex<f64>
+    lower_right: Complex<f64>,
+    fractal: FractalSpec,
+    style: RenderStyle
 ) {
-    assert!(pixels.len() == bounds.0 * bounds.1);
+    assert!(pixels.len() == bounds.0 * bounds.1 * 3);
 
     for row in 0..bounds.1 {
         for col in 0..bounds.0 {
             let point = pixel_to_point(bounds, (col, row), upper_left, lower_right);
 
-            pixels[row * bounds.0 + col] = match escape_time(point, 255) {
-                None => 0,
-                Some(count) => 255 - count as u8
+            let color = match escape_time(point, fractal) {
+                None => style.interior,
+                Some(escape) => {
+                    let count = if style.smooth {
+                        smooth_escape_count(escape)
+                    } else {
+                        escape.count as f64
+                    };
+
+                    style.palette.color(count / fractal.limit as f64 * 255.0)
+                }
             };
+
+            let index = (row * bounds.0 + col) * 3;
+            pixels[index..index + 3].copy_from_slice(&color);
+        }
+    }
+}
+
+/// Render a rectangle of the Mandelbrot set into a buffer of pixels,
+/// splitting the work across however many threads Rayon's global pool has
+/// available.
+///
+/// Unlike `render`, which does the whole image on the calling thread, this
+/// hands Rayon one task per row: `pixels` is sliced into row-sized chunks
+/// with `chunks_mut`, and each chunk is rendered independently via
+/// `into_par_iter`. Splitting per row (rather than into a handful of fixed
+/// bands) lets Rayon's work-stealing scheduler keep every thread busy even
+/// though rows near the set's boundary take far longer to escape than rows
+/// deep in the interior or far outside it.
+///
+/// If `progress` is given, it's called after each row finishes with the
+/// fraction of rows completed so far (`0.0..=1.0`). It may be called from
+/// any of Rayon's worker threads and in any order, so callers that aren't
+/// content with a best-effort, possibly out-of-order progress bar should
+/// do their own synchronization.
+pub fn render_parallel(
+    pixels: &mut [u8],
+    bounds: (usize, usize),
+    upper_left: Complex<f64>,
+    lower_right: Complex<f64>,
+    fractal: FractalSpec,
+    style: RenderStyle,
+    progress: Option<&(dyn Fn(f64) + Sync)>
+) {
+    assert!(pixels.len() == bounds.0 * bounds.1 * 3);
+
+    let completed_rows = AtomicUsize::new(0);
+    let row_pixels: Vec<&mut [u8]> = pixels.chunks_mut(bounds.0 * 3).collect();
+
+    row_pixels
+        .into_par_iter()
+        .enumerate()
+        .for_each(|(row, row_pixels)| {
+            let row_upper_left = pixel_to_point(bounds, (0, row), upper_left, lower_right);
+            let row_lower_right = pixel_to_point(bounds, (bounds.0, row + 1), upper_left, lower_right);
+
+            render(row_pixels, (bounds.0, 1), row_upper_left, row_lower_right, fractal, style);
+
+            if let Some(progress) = progress {
+                let done = completed_rows.fetch_add(1, Ordering::Relaxed) + 1;
+                progress(done as f64 / bounds.1 as f64);
+            }
+        });
+}
+
+/// Render a Buddhabrot plot into a buffer of pixels.
+///
+/// Rather than coloring each pixel by its own escape time, this samples
+/// `samples` random points `c` across the `upper_left`/`lower_right`
+/// rectangle and iterates each up to `fractal.limit` times. Points that
+/// never escape contribute nothing; their orbits are discarded entirely.
+/// For every point that does escape, its orbit is replayed from `z = 0`
+/// and each intermediate `z` that falls inside the view increments that
+/// pixel's hit counter. The counters are then normalized so the busiest
+/// pixel maps to 255, producing a grayscale image (replicated across the
+/// RGB channels of the `pixels` buffer).
+///
+/// If `progress` is given, it's called after each sample with the fraction
+/// of samples completed so far (`0.0..=1.0`).
+pub fn render_buddhabrot(
+    pixels: &mut [u8],
+    bounds: (usize, usize),
+    upper_left: Complex<f64>,
+    lower_right: Complex<f64>,
+    samples: u32,
+    fractal: FractalSpec,
+    progress: Option<&dyn Fn(f64)>
+) {
+    assert!(pixels.len() == bounds.0 * bounds.1 * 3);
+
+    let mut hits = vec![0u32; bounds.0 * bounds.1];
+    let mut rng = rand::thread_rng();
+
+    for sample in 0..samples {
+        let c = Complex {
+            re: rng.gen_range(upper_left.re..lower_right.re),
+            im: rng.gen_range(lower_right.im..upper_left.im),
+        };
+
+        if escape_time(c, fractal).is_none() {
+            if let Some(progress) = progress {
+                progress((sample + 1) as f64 / samples as f64);
+            }
+
+            continue;
+        }
+
+        let mut z = Complex { re: 0.0, im: 0.0 };
+
+        for _ in 0..fractal.limit {
+            z = step(z, c, fractal.kind);
+
+            if z.norm_sqr() > 4.0 {
+                break;
+            }
+
+            if let Some((col, row)) = point_to_pixel(bounds, z, upper_left, lower_right) {
+                hits[row * bounds.0 + col] += 1;
+            }
+        }
+
+        if let Some(progress) = progress {
+            progress((sample + 1) as f64 / samples as f64);
         }
     }
+
+    let max = hits.iter().cloned().max().unwrap_or(0).max(1);
+
+    for (index, &hit) in hits.iter().enumerate() {
+        let gray = (hit as f64 / max as f64 * 255.0) as u8;
+        pixels[index * 3..index * 3 + 3].copy_from_slice(&[gray, gray, gray]);
+    }
 }
 
-/// Write the contents of the `pixel` buffer, whose dimensions are given
-/// by `bounds`, to the specified file.
+/// Write the contents of the `pixels` buffer (3 bytes per pixel, RGB),
+/// whose dimensions are given by `bounds`, to `filename`.
+///
+/// The format is chosen from `filename`'s extension: `.png` is encoded
+/// through the `image` crate; `.ppm` is written by hand as a raw NetPBM
+/// color bitmap; `.pgm` is written by hand as a raw NetPBM grayscale
+/// bitmap, first collapsing each RGB pixel to a single byte by averaging
+/// its channels.
+/// Any other extension is an error.
 pub fn write_image(
-    filename: &str, 
-    pixels: &[u8], 
+    filename: &str,
+    pixels: &[u8],
+    bounds: (usize, usize)
+) -> Result<(), std::io::Error> {
+    assert!(pixels.len() == bounds.0 * bounds.1 * 3);
+
+    match Path::new(filename).extension().and_then(|ext| ext.to_str()) {
+        Some("png") => write_png(filename, pixels, bounds),
+        Some("ppm") => write_ppm(filename, pixels, bounds),
+        Some("pgm") => write_pgm(filename, pixels, bounds),
+        _ => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("don't know how to write an image to '{}'", filename)
+        ))
+    }
+}
+
+/// Encode `pixels` as a PNG.
+fn write_png(
+    filename: &str,
+    pixels: &[u8],
     bounds: (usize, usize)
 ) -> Result<(), std::io::Error> {
     let output = File::create(filename)?;
     let encoder = PNGEncoder::new(output);
 
     encoder.encode(
-        &pixels, 
-        bounds.0 as u32, 
-        bounds.1 as u32, 
-        ColorType::Gray(8)
+        &pixels,
+        bounds.0 as u32,
+        bounds.1 as u32,
+        ColorType::RGB(8)
     )?;
 
     Ok(())
 }
 
+/// Write `pixels` as a raw (binary) NetPBM color bitmap (`P6`).
+fn write_ppm(
+    filename: &str,
+    pixels: &[u8],
+    bounds: (usize, usize)
+) -> Result<(), std::io::Error> {
+    let mut output = File::create(filename)?;
+
+    write!(output, "P6\n{} {}\n255\n", bounds.0, bounds.1)?;
+    output.write_all(pixels)?;
+
+    Ok(())
+}
+
+/// Write `pixels` as a raw (binary) NetPBM grayscale bitmap (`P5`),
+/// collapsing each RGB pixel to a single byte by averaging its channels.
+fn write_pgm(
+    filename: &str,
+    pixels: &[u8],
+    bounds: (usize, usize)
+) -> Result<(), std::io::Error> {
+    let mut output = File::create(filename)?;
+
+    write!(output, "P5\n{} {}\n255\n", bounds.0, bounds.1)?;
+
+    let gray: Vec<u8> = pixels
+        .chunks(3)
+        .map(|rgb| ((rgb[0] as u32 + rgb[1] as u32 + rgb[2] as u32) / 3) as u8)
+        .collect();
+
+    output.write_all(&gray)?;
+
+    Ok(())
+}
+
 #[test]
 fn test_parse_pair() {
     assert_eq!(parse_pair::<i32>("",        ','), None);
@@ -140,10 +523,149 @@ fn test_parse_complex() {
     assert_eq!(parse_complex(",-0.0625"), None);
 }
 
+#[test]
+fn test_smooth_escape_count() {
+    let escape = Escape { count: 10, z: Complex { re: 3.0, im: 0.0 } };
+    let mu = smooth_escape_count(escape);
+
+    assert!(mu > 10.0 && mu < 11.0);
+}
+
+#[test]
+fn test_fractal_kind_from_str() {
+    assert_eq!("mandelbrot".parse(), Ok(FractalKind::Mandelbrot));
+    assert_eq!("mandelbrot3".parse(), Ok(FractalKind::Mandelbrot3));
+    assert_eq!("burning-ship".parse(), Ok(FractalKind::BurningShip));
+    assert!("nonsense".parse::<FractalKind>().is_err());
+}
+
+#[test]
+fn test_palette_from_str() {
+    assert_eq!("grayscale".parse(), Ok(Palette::Grayscale));
+    assert_eq!("fire".parse(), Ok(Palette::Fire));
+    assert_eq!("blue-gold".parse(), Ok(Palette::BlueGold));
+    assert_eq!("hsv".parse(), Ok(Palette::Hsv));
+    assert!("nonsense".parse::<Palette>().is_err());
+}
+
+#[test]
+fn test_palette_grayscale_color() {
+    assert_eq!(Palette::Grayscale.color(0.0), [255, 255, 255]);
+    assert_eq!(Palette::Grayscale.color(255.0), [0, 0, 0]);
+    // Out-of-range counts are clamped rather than wrapping.
+    assert_eq!(Palette::Grayscale.color(-10.0), [255, 255, 255]);
+    assert_eq!(Palette::Grayscale.color(300.0), [0, 0, 0]);
+}
+
+#[test]
+fn test_palette_fire_color() {
+    assert_eq!(Palette::Fire.color(0.0), [0, 0, 0]);
+    assert_eq!(Palette::Fire.color(255.0), [255, 255, 255]);
+}
+
+#[test]
+fn test_palette_blue_gold_color() {
+    assert_eq!(Palette::BlueGold.color(0.0), [0, 40, 200]);
+    assert_eq!(Palette::BlueGold.color(255.0), [212, 215, 0]);
+}
+
+#[test]
+fn test_palette_hsv_color() {
+    assert_eq!(Palette::Hsv.color(0.0), [255, 0, 0]);
+    assert_eq!(Palette::Hsv.color(255.0 / 3.0), [0, 255, 0]);
+    assert_eq!(Palette::Hsv.color(255.0 * 2.0 / 3.0), [0, 0, 255]);
+}
+
 #[test]
 fn test_pixel_to_point() {
     assert_eq!(pixel_to_point((100, 100), (25, 75),
                               Complex { re: -1.0, im:  1.0 },
                               Complex { re:  1.0, im: -1.0 }),
-               Complex { re: -0.5, im: -0.5 }); 
+               Complex { re: -0.5, im: -0.5 });
+}
+
+#[test]
+fn test_point_to_pixel() {
+    assert_eq!(point_to_pixel((100, 100),
+                              Complex { re: -0.5, im: -0.5 },
+                              Complex { re: -1.0, im:  1.0 },
+                              Complex { re:  1.0, im: -1.0 }),
+               Some((25, 75)));
+
+    assert_eq!(point_to_pixel((100, 100),
+                              Complex { re: -2.0, im: -0.5 },
+                              Complex { re: -1.0, im:  1.0 },
+                              Complex { re:  1.0, im: -1.0 }),
+               None);
+}
+
+#[test]
+fn test_render_buddhabrot_all_zero_when_every_orbit_escapes_immediately() {
+    // Every sampled `c` lies well outside the radius-2 escape circle, so
+    // each orbit escapes on its very first step, before it can ever be
+    // mapped back into the (equally far-away) view rectangle.
+    let bounds = (4, 4);
+    let mut pixels = vec![0u8; bounds.0 * bounds.1 * 3];
+    let upper_left = Complex { re: 10.0, im: 10.0 };
+    let lower_right = Complex { re: 20.0, im: -10.0 };
+    let fractal = FractalSpec { kind: FractalKind::Mandelbrot, limit: 10 };
+
+    render_buddhabrot(&mut pixels, bounds, upper_left, lower_right, 20, fractal, None);
+
+    assert!(pixels.iter().all(|&byte| byte == 0));
+}
+
+#[test]
+fn test_render_buddhabrot_populates_some_pixel() {
+    let bounds = (20, 20);
+    let mut pixels = vec![0u8; bounds.0 * bounds.1 * 3];
+    let upper_left = Complex { re: -2.0, im: 1.5 };
+    let lower_right = Complex { re: 1.0, im: -1.5 };
+    let fractal = FractalSpec { kind: FractalKind::Mandelbrot, limit: 50 };
+
+    render_buddhabrot(&mut pixels, bounds, upper_left, lower_right, 2000, fractal, None);
+
+    assert!(pixels.iter().any(|&byte| byte > 0));
+}
+
+#[test]
+fn test_write_image_rejects_unrecognized_extension() {
+    let pixels = [0u8, 0, 0];
+
+    let err = write_image("test_output.exr", &pixels, (1, 1)).unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_write_image_ppm_round_trip() {
+    let bounds = (2, 1);
+    let pixels = [10u8, 20, 30, 40, 50, 60];
+    let path = std::env::temp_dir().join("mandelbrot_test_write_image.ppm");
+
+    write_image(path.to_str().unwrap(), &pixels, bounds).unwrap();
+    let contents = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let mut expected = b"P6\n2 1\n255\n".to_vec();
+    expected.extend_from_slice(&pixels);
+
+    assert_eq!(contents, expected);
+}
+
+#[test]
+fn test_write_image_pgm_round_trip() {
+    let bounds = (2, 1);
+    let pixels = [10u8, 20, 30, 90, 90, 90];
+    let path = std::env::temp_dir().join("mandelbrot_test_write_image.pgm");
+
+    write_image(path.to_str().unwrap(), &pixels, bounds).unwrap();
+    let contents = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let mut expected = b"P5\n2 1\n255\n".to_vec();
+    expected.push(20); // (10 + 20 + 30) / 3
+    expected.push(90); // (90 + 90 + 90) / 3
+
+    assert_eq!(contents, expected);
 }